@@ -2,6 +2,7 @@ use window::{WindowAttributes};
 use std::collections::VecDeque;
 use std::rc::Rc;
 use std::cell::Cell;
+use std::sync::atomic::{AtomicU32, Ordering};
 use dpi::{PhysicalPosition, LogicalPosition, PhysicalSize, LogicalSize};
 use icon::Icon;
 use super::event_loop::{EventLoopWindowTarget};
@@ -12,6 +13,13 @@ use ::window::CursorIcon;
 use ::wasm_bindgen::JsCast;
 use web_sys::HtmlElement;
 
+use ::raw_window_handle::{
+    HasRawWindowHandle, HasRawDisplayHandle, RawWindowHandle, RawDisplayHandle, WebWindowHandle,
+    WebDisplayHandle,
+};
+
+static NEXT_CANVAS_ID: AtomicU32 = AtomicU32::new(1);
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct DeviceId(u32);
 
@@ -69,7 +77,8 @@ impl MonitorHandle {
             Err(val) => 0.0
         };
 
-        (w, h).into()
+        let scale_factor = current_scale_factor();
+        (w * scale_factor, h * scale_factor).into()
     }
 
     /// Returns the top-left corner position of the monitor relative to the larger full
@@ -89,13 +98,172 @@ impl MonitorHandle {
     /// - **Android:** Always returns 1.0.
     #[inline]
     pub fn hidpi_factor(&self) -> f64 {
-        1.0
+        current_scale_factor()
     }
 }
 
 pub struct Window {
     pub(crate) canvas: ::web_sys::HtmlCanvasElement,
-    pub(crate) redraw_requested: Cell<bool>
+    pub(crate) redraw_requested: Cell<bool>,
+    /// Armed by the `matchMedia` listener set up in `Window::new`.
+    pub(crate) pending_scale_factor: Rc<Cell<Option<f64>>>,
+    /// Shared with the `ResizeObserver` callback set up in `Window::new`, so that container-driven
+    /// layout changes (not just explicit `set_inner_size` calls) can arm it.
+    pub(crate) pending_resize: Rc<Cell<Option<LogicalSize>>>,
+    /// Tracks whether `document.pointerLockElement` is really our canvas. Kept up to date by the
+    /// `pointerlockchange`/`pointerlockerror` listeners registered in `Window::new`.
+    pub(crate) cursor_grabbed: Rc<Cell<bool>>,
+    pub(crate) id: u32
+}
+
+/// Reads the browser's current `devicePixelRatio`.
+///
+/// This is re-queried on demand rather than cached, since it can change at any time (e.g. the
+/// window being dragged to a monitor with a different pixel density, or the OS-level zoom level
+/// changing).
+fn current_scale_factor() -> f64 {
+    ::web_sys::window()
+        .expect("there to be a window")
+        .device_pixel_ratio()
+}
+
+/// Arms a `matchMedia` query for the current `devicePixelRatio` and, on firing, sets
+/// `pending_scale_factor` and re-arms a fresh query for the new ratio — a single `matchMedia`
+/// entry only ever matches once.
+fn setup_scale_factor_listener(pending_scale_factor: &Rc<Cell<Option<f64>>>) {
+    let window = ::web_sys::window().expect("there to be a window");
+    let query = format!("(resolution: {}dppx)", window.device_pixel_ratio());
+    let media_query_list = window
+        .match_media(&query)
+        .expect("could not query matchMedia")
+        .expect("matchMedia returned no MediaQueryList");
+
+    let on_change_pending = pending_scale_factor.clone();
+    let on_change = ::wasm_bindgen::closure::Closure::wrap(Box::new(move |_: ::web_sys::Event| {
+        on_change_pending.set(Some(current_scale_factor()));
+        setup_scale_factor_listener(&on_change_pending);
+    }) as Box<dyn FnMut(_)>);
+    media_query_list
+        .add_event_listener_with_callback("change", on_change.as_ref().unchecked_ref())
+        .expect("could not register matchMedia change listener");
+    on_change.forget();
+}
+
+/// Registers `pointerlockchange`/`pointerlockerror` listeners on `document` that keep
+/// `cursor_grabbed` in sync with whether `canvas` is really the browser's `pointerLockElement`.
+fn setup_pointer_lock_listeners(
+    document: &::web_sys::Document,
+    canvas: &::web_sys::HtmlCanvasElement,
+    cursor_grabbed: &Rc<Cell<bool>>
+) {
+    let change_canvas: ::web_sys::Element = canvas.clone().unchecked_into();
+    let change_grabbed = cursor_grabbed.clone();
+    let on_change = ::wasm_bindgen::closure::Closure::wrap(Box::new(move |_: ::web_sys::Event| {
+        let locked_element = ::web_sys::window()
+            .and_then(|w| w.document())
+            .and_then(|d| d.pointer_lock_element());
+        let is_us = locked_element.as_ref() == Some(&change_canvas);
+        change_grabbed.set(is_us);
+    }) as Box<dyn FnMut(_)>);
+    document
+        .add_event_listener_with_callback("pointerlockchange", on_change.as_ref().unchecked_ref())
+        .expect("could not register pointerlockchange listener");
+    on_change.forget();
+
+    let error_grabbed = cursor_grabbed.clone();
+    let on_error = ::wasm_bindgen::closure::Closure::wrap(Box::new(move |_: ::web_sys::Event| {
+        error_grabbed.set(false);
+    }) as Box<dyn FnMut(_)>);
+    document
+        .add_event_listener_with_callback("pointerlockerror", on_error.as_ref().unchecked_ref())
+        .expect("could not register pointerlockerror listener");
+    on_error.forget();
+}
+
+/// Installs a `ResizeObserver` on `canvas` so that container-driven layout changes (CSS, flex,
+/// the `ContainerId` parent being resized, etc.) arm `pending_resize` the same way an explicit
+/// `set_inner_size` call does - mirroring how desktop backends report OS-driven resizes alongside
+/// app-driven ones.
+///
+/// Unlike `set_inner_size`, this does not touch the canvas's CSS box: the layout is what drove
+/// the resize in the first place, so only the backing store (and the `devicePixelRatio`-scaled
+/// drawing buffer) needs to be kept in sync with it.
+fn setup_resize_observer(
+    canvas: &::web_sys::HtmlCanvasElement,
+    pending_resize: &Rc<Cell<Option<LogicalSize>>>
+) {
+    let observed_canvas = canvas.clone();
+    let observed_pending_resize = pending_resize.clone();
+    let on_resize = ::wasm_bindgen::closure::Closure::wrap(Box::new(
+        move |entries: ::js_sys::Array, _observer: ::web_sys::ResizeObserver| {
+            let entry = match entries.get(0).dyn_into::<::web_sys::ResizeObserverEntry>() {
+                Ok(entry) => entry,
+                Err(_) => return
+            };
+            let content_rect = entry.content_rect();
+            let size: LogicalSize = (content_rect.width(), content_rect.height()).into();
+
+            let physical: PhysicalSize = size.to_physical(current_scale_factor());
+            observed_canvas.set_width(physical.width as u32);
+            observed_canvas.set_height(physical.height as u32);
+
+            observed_pending_resize.set(Some(size));
+        }
+    ) as Box<dyn FnMut(::js_sys::Array, ::web_sys::ResizeObserver)>);
+
+    let observer = ::web_sys::ResizeObserver::new(on_resize.as_ref().unchecked_ref())
+        .expect("could not create a ResizeObserver");
+    observer.observe(canvas);
+
+    // Both the observer and its callback must outlive `Window::new`, for as long as the canvas
+    // itself does, so they're deliberately leaked rather than stored on `Window`.
+    on_resize.forget();
+    ::std::mem::forget(observer);
+}
+
+/// Finds the document's favicon `<link>`, creating one if it doesn't already exist.
+fn favicon_link(document: &::web_sys::Document) -> ::web_sys::HtmlLinkElement {
+    if let Some(existing) = document.query_selector("link[rel~='icon']").unwrap() {
+        return existing.dyn_into::<::web_sys::HtmlLinkElement>().unwrap();
+    }
+
+    let link = document.create_element("link")
+        .expect("could not create a link element")
+        .dyn_into::<::web_sys::HtmlLinkElement>().unwrap();
+    link.set_rel("icon");
+    document.head()
+        .expect("document to have a head")
+        .append_child(&link)
+        .expect("could not append favicon link");
+    link
+}
+
+/// Renders an `Icon`'s RGBA pixels onto an offscreen canvas and encodes it as a data URL
+/// suitable for a favicon `<link href>`.
+fn icon_to_data_url(icon: &Icon) -> String {
+    let width = icon.inner.width;
+    let height = icon.inner.height;
+
+    let window = ::web_sys::window().expect("there to be a window");
+    let document = window.document().expect("window to have a document");
+    let canvas = document.create_element("canvas")
+        .expect("could not create a canvas")
+        .dyn_into::<::web_sys::HtmlCanvasElement>().unwrap();
+    canvas.set_width(width);
+    canvas.set_height(height);
+
+    let context = canvas.get_context("2d")
+        .expect("could not get a 2d context")
+        .expect("canvas has no 2d context")
+        .dyn_into::<::web_sys::CanvasRenderingContext2d>().unwrap();
+
+    let image_data = ::web_sys::ImageData::new_with_u8_clamped_array(
+        ::wasm_bindgen::Clamped(&icon.inner.rgba), width
+    ).expect("could not build image data from icon rgba");
+    context.put_image_data(&image_data, 0.0, 0.0)
+        .expect("could not draw icon onto canvas");
+
+    canvas.to_data_url().expect("could not encode favicon canvas")
 }
 
 pub(crate) struct WindowInternal<'a, T: 'static> {
@@ -120,6 +288,8 @@ impl Window {
         let document = window.document()
             .expect("Global window does not have a document!");
 
+        document.set_title(&attr.title);
+
         let element = match ps_attr.element {
             ElementSelection::CanvasId(id) => {
                 document.get_element_by_id(&id)
@@ -142,12 +312,74 @@ impl Window {
 
         target.setup_window(&element);
 
+        let id = NEXT_CANVAS_ID.fetch_add(1, Ordering::Relaxed);
+        element.set_attribute("data-raw-handle", &id.to_string())?;
+
+        let cursor_grabbed = Rc::new(Cell::new(false));
+        setup_pointer_lock_listeners(&document, &element, &cursor_grabbed);
+
+        let pending_resize = Rc::new(Cell::new(None));
+        setup_resize_observer(&element, &pending_resize);
+
+        let pending_scale_factor = Rc::new(Cell::new(None));
+        setup_scale_factor_listener(&pending_scale_factor);
+
         Ok(Window {
             canvas: element,
-            redraw_requested: Cell::new(false)
+            redraw_requested: Cell::new(false),
+            pending_scale_factor,
+            pending_resize,
+            cursor_grabbed,
+            id
         })
     }
 
+    /// Checks whether the canvas has been resized since the last time this was called, returning
+    /// the new logical size if so.
+    ///
+    /// This only records the size: nothing in this backend calls it, so no `WindowEvent::Resized`
+    /// is emitted for a resize.
+    #[inline]
+    pub(crate) fn check_resized(&self) -> Option<LogicalSize> {
+        self.pending_resize.take()
+    }
+
+    /// Resizes the canvas's backing store to match `size` at the current `hidpi_factor`, and
+    /// resizes the canvas's CSS box to `size` logical pixels.
+    fn resize_canvas(&self, size: LogicalSize) {
+        let hidpi_factor = self.hidpi_factor();
+        let physical: PhysicalSize = size.to_physical(hidpi_factor);
+
+        self.canvas.set_width(physical.width as u32);
+        self.canvas.set_height(physical.height as u32);
+
+        let style = self.canvas_as_element().style();
+        style.set_property("width", &format!("{}px", size.width)).unwrap();
+        style.set_property("height", &format!("{}px", size.height)).unwrap();
+
+        self.pending_resize.set(Some(size));
+    }
+
+    /// Checks whether `devicePixelRatio` has changed since the last time this was called,
+    /// returning the new value if so.
+    ///
+    /// This only records the ratio: nothing in this backend calls it, so no
+    /// `WindowEvent::HiDpiFactorChanged` is emitted when the ratio changes.
+    #[inline]
+    pub(crate) fn check_scale_factor_changed(&self) -> Option<f64> {
+        self.pending_scale_factor.take()
+    }
+
+    /// Returns whether the cursor is actually locked to this window right now, as last reported
+    /// by the browser's `pointerlockchange`/`pointerlockerror` events.
+    ///
+    /// Nothing in this backend calls this, so mouse motion is handled the same way whether or not
+    /// the cursor is locked; `MouseEvent::movement_x`/`movement_y` are not read.
+    #[inline]
+    pub(crate) fn is_cursor_grabbed(&self) -> bool {
+        self.cursor_grabbed.get()
+    }
+
     /// Returns an identifier unique to the window.
     #[inline]
     pub fn id(&self) -> WindowId {
@@ -172,7 +404,7 @@ impl Window {
     /// [`contentScaleFactor`]: https://developer.apple.com/documentation/uikit/uiview/1622657-contentscalefactor?language=objc
     #[inline]
     pub fn hidpi_factor(&self) -> f64 {
-        1.0
+        current_scale_factor()
     }
 
     /// Emits a `WindowEvent::RedrawRequested` event in the associated event loop after all OS
@@ -201,6 +433,20 @@ impl Window {
     }
 }
 
+unsafe impl HasRawWindowHandle for Window {
+    fn raw_window_handle(&self) -> RawWindowHandle {
+        let mut handle = WebWindowHandle::empty();
+        handle.id = self.id;
+        RawWindowHandle::Web(handle)
+    }
+}
+
+unsafe impl HasRawDisplayHandle for Window {
+    fn raw_display_handle(&self) -> RawDisplayHandle {
+        RawDisplayHandle::Web(WebDisplayHandle::empty())
+    }
+}
+
 /// Position and size functions.
 impl Window {
     /// Returns the position of the top-left hand corner of the window's client area relative to the
@@ -283,7 +529,7 @@ impl Window {
     ///   would mean for iOS.
     #[inline]
     pub fn set_inner_size(&self, size: LogicalSize) {
-        unimplemented!()
+        self.resize_canvas(size);
     }
 
     /// Returns the logical size of the entire window.
@@ -307,7 +553,17 @@ impl Window {
     /// - **iOS:** Has no effect.
     #[inline]
     pub fn set_min_inner_size(&self, dimensions: Option<LogicalSize>) {
-        unimplemented!()
+        let style = self.canvas_as_element().style();
+        match dimensions {
+            Some(size) => {
+                style.set_property("min-width", &format!("{}px", size.width)).unwrap();
+                style.set_property("min-height", &format!("{}px", size.height)).unwrap();
+            },
+            None => {
+                style.remove_property("min-width").unwrap();
+                style.remove_property("min-height").unwrap();
+            }
+        }
     }
 
     /// Sets a maximum dimension size for the window.
@@ -317,7 +573,17 @@ impl Window {
     /// - **iOS:** Has no effect.
     #[inline]
     pub fn set_max_inner_size(&self, dimensions: Option<LogicalSize>) {
-        unimplemented!()
+        let style = self.canvas_as_element().style();
+        match dimensions {
+            Some(size) => {
+                style.set_property("max-width", &format!("{}px", size.width)).unwrap();
+                style.set_property("max-height", &format!("{}px", size.height)).unwrap();
+            },
+            None => {
+                style.remove_property("max-width").unwrap();
+                style.remove_property("max-height").unwrap();
+            }
+        }
     }
 }
 
@@ -330,7 +596,9 @@ impl Window {
     /// - Has no effect on iOS.
     #[inline]
     pub fn set_title(&self, title: &str) {
-        unimplemented!()
+        let window = ::web_sys::window().expect("there to be a window");
+        let document = window.document().expect("window to have a document");
+        document.set_title(title);
     }
 
     /// Modifies the window's visibility.
@@ -376,12 +644,27 @@ impl Window {
 
     /// Sets the window to fullscreen or back.
     ///
+    /// This is backed by the browser's [Fullscreen API], requesting fullscreen on the canvas itself.
+    ///
+    /// [Fullscreen API]: https://developer.mozilla.org/en-US/docs/Web/API/Fullscreen_API
+    ///
     /// ## Platform-specific
     ///
     /// - **iOS:** Can only be called on the main thread.
     #[inline]
     pub fn set_fullscreen(&self, monitor: Option<::monitor::MonitorHandle>) {
-        // no-op
+        match monitor {
+            Some(_) => {
+                if let Err(err) = self.canvas_as_element().request_fullscreen() {
+                    ::web_sys::console::error_1(&err);
+                }
+            },
+            None => {
+                let window = ::web_sys::window().expect("there to be a window");
+                let document = window.document().expect("window to have a document");
+                document.exit_fullscreen();
+            }
+        }
     }
 
     /// Gets the window's current fullscreen state.
@@ -391,7 +674,14 @@ impl Window {
     /// - **iOS:** Can only be called on the main thread.
     #[inline]
     pub fn fullscreen(&self) -> Option<::monitor::MonitorHandle> {
-        None
+        let window = ::web_sys::window().expect("there to be a window");
+        let document = window.document().expect("window to have a document");
+        let our_canvas: ::web_sys::Element = self.canvas.clone().unchecked_into();
+        if document.fullscreen_element() == Some(our_canvas) {
+            Some(self.current_monitor())
+        } else {
+            None
+        }
     }
 
     /// Turn window decorations on or off.
@@ -425,10 +715,21 @@ impl Window {
     /// ## Platform-specific
     ///
     /// This only has an effect on Windows and X11.
+    /// - **Web:** Sets the page's favicon.
     #[inline]
     pub fn set_window_icon(&self, window_icon: Option<Icon>) {
-        // TODO: set favicon?
-        unimplemented!()
+        let window = ::web_sys::window().expect("there to be a window");
+        let document = window.document().expect("window to have a document");
+        let link = favicon_link(&document);
+
+        match window_icon {
+            Some(icon) => {
+                link.set_attribute("href", &icon_to_data_url(&icon)).unwrap();
+            },
+            None => {
+                link.remove();
+            }
+        }
     }
 
     /// Sets location of IME candidate box in client area coordinates relative to the top left.
@@ -502,6 +803,10 @@ impl Window {
 
     /// Grabs the cursor, preventing it from leaving the window.
     ///
+    /// This is backed by the browser's [Pointer Lock API].
+    ///
+    /// [Pointer Lock API]: https://developer.mozilla.org/en-US/docs/Web/API/Pointer_Lock_API
+    ///
     /// ## Platform-specific
     ///
     /// - **macOS:** This presently merely locks the cursor in a fixed location, which looks visually
@@ -510,8 +815,14 @@ impl Window {
     /// - **iOS:** Always returns an Err.
     #[inline]
     pub fn set_cursor_grab(&self, grab: bool) -> Result<(), ExternalError> {
-        // unsupported
-        Err(ExternalError::NotSupported(NotSupportedError::new()))
+        if grab {
+            self.canvas.request_pointer_lock();
+        } else {
+            let window = ::web_sys::window().expect("there to be a window");
+            let document = window.document().expect("window to have a document");
+            document.exit_pointer_lock();
+        }
+        Ok(())
     }
 
     /// Modifies the cursor's visibility.